@@ -3,6 +3,7 @@ use std::{
     io::{stdin, stdout, BufRead, IsTerminal, Read, Write},
     iter,
     path::PathBuf,
+    rc::Rc,
 };
 
 use anyhow::{anyhow, Context, Result};
@@ -50,6 +51,30 @@ struct Cli {
     #[arg(short = 'T', long, group = "format")]
     toml: bool,
 
+    /// Enable cbor for both input and output
+    #[arg(long, group = "format")]
+    cbor: bool,
+
+    /// Enable msgpack for both input and output
+    #[arg(long, group = "format")]
+    msgpack: bool,
+
+    /// Enable ron for both input and output
+    #[arg(long, group = "format")]
+    ron: bool,
+
+    /// Enable json5 for both input and output
+    #[arg(long, group = "format")]
+    json5: bool,
+
+    /// Enable csv for both input and output
+    #[arg(long, group = "format")]
+    csv: bool,
+
+    /// Enable tsv for both input and output
+    #[arg(long, group = "format")]
+    tsv: bool,
+
     #[clap(flatten)]
     input_format: InputFormatArg,
 
@@ -66,6 +91,12 @@ enum SerializationFormat {
     Json,
     Yaml,
     Toml,
+    Cbor,
+    Msgpack,
+    Ron,
+    Json5,
+    Csv,
+    Tsv,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, clap::Args)]
@@ -91,12 +122,45 @@ struct InputFormatArg {
     #[arg(long, group = "input-format", conflicts_with = "format")]
     toml_input: bool,
 
+    /// Read input as cbor values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    cbor_input: bool,
+
+    /// Read input as msgpack values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    msgpack_input: bool,
+
+    /// Read input as ron values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    ron_input: bool,
+
+    /// Read input as json5 values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    json5_input: bool,
+
+    /// Read input as csv values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    csv_input: bool,
+
+    /// Read input as tsv values
+    #[arg(long, group = "input-format", conflicts_with = "format")]
+    tsv_input: bool,
+
+    /// Treat each csv/tsv record as an array of strings instead of an object keyed by the
+    /// header row
+    #[arg(long)]
+    no_header: bool,
+
     /// Treat each line of input will be supplied to the filter as a string.
     /// When used with --slurp, the whole input text will be supplied to the filter as a single
     /// string.
     #[arg(short = 'R', long, group = "input-format")]
     raw_input: bool,
 
+    /// Split raw input on NUL bytes instead of newlines. Requires --raw-input.
+    #[arg(long, requires = "raw_input")]
+    raw_input0: bool,
+
     /// Single null is supplied to the program.
     /// The original input can still be read via input/0 and inputs/0.
     #[arg(short, long)]
@@ -131,10 +195,39 @@ struct OutputFormatArg {
     #[arg(long, group = "output-format", conflicts_with = "format")]
     toml_output: bool,
 
+    /// Write output as cbor values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    cbor_output: bool,
+
+    /// Write output as msgpack values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    msgpack_output: bool,
+
+    /// Write output as ron values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    ron_output: bool,
+
+    /// Write output as json5 values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    json5_output: bool,
+
+    /// Write output as csv values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    csv_output: bool,
+
+    /// Write output as tsv values
+    #[arg(long, group = "output-format", conflicts_with = "format")]
+    tsv_output: bool,
+
     /// Output raw string if the output value was a string
     #[clap(short, long, conflicts_with = "output-format")]
     raw_output: bool,
 
+    /// Like --raw-output, but separate outputs with a NUL byte instead of a newline, so the
+    /// output can be fed safely into tools like `xargs -0`
+    #[clap(short = '0', long, conflicts_with = "output-format")]
+    raw_output0: bool,
+
     /// Compact output
     #[clap(short, long, conflicts_with = "output-format")]
     compact_output: bool,
@@ -152,6 +245,18 @@ impl Cli {
             return SerializationFormat::Yaml;
         } else if self.toml || self.input_format.toml_input {
             return SerializationFormat::Toml;
+        } else if self.cbor || self.input_format.cbor_input {
+            return SerializationFormat::Cbor;
+        } else if self.msgpack || self.input_format.msgpack_input {
+            return SerializationFormat::Msgpack;
+        } else if self.ron || self.input_format.ron_input {
+            return SerializationFormat::Ron;
+        } else if self.json5 || self.input_format.json5_input {
+            return SerializationFormat::Json5;
+        } else if self.csv || self.input_format.csv_input {
+            return SerializationFormat::Csv;
+        } else if self.tsv || self.input_format.tsv_input {
+            return SerializationFormat::Tsv;
         } else {
             // If no options were specified, attempt to parse from the input file extension
             if let Some(path) = &self.file {
@@ -160,6 +265,12 @@ impl Cli {
                         "json" => return SerializationFormat::Json,
                         "yaml" => return SerializationFormat::Yaml,
                         "toml" => return SerializationFormat::Toml,
+                        "cbor" => return SerializationFormat::Cbor,
+                        "msgpack" => return SerializationFormat::Msgpack,
+                        "ron" => return SerializationFormat::Ron,
+                        "json5" => return SerializationFormat::Json5,
+                        "csv" => return SerializationFormat::Csv,
+                        "tsv" => return SerializationFormat::Tsv,
                         _ => {}
                     };
                 }
@@ -176,6 +287,18 @@ impl Cli {
             SerializationFormat::Yaml
         } else if self.toml || self.output_format.toml_output {
             SerializationFormat::Toml
+        } else if self.cbor || self.output_format.cbor_output {
+            SerializationFormat::Cbor
+        } else if self.msgpack || self.output_format.msgpack_output {
+            SerializationFormat::Msgpack
+        } else if self.ron || self.output_format.ron_output {
+            SerializationFormat::Ron
+        } else if self.json5 || self.output_format.json5_output {
+            SerializationFormat::Json5
+        } else if self.csv || self.output_format.csv_output {
+            SerializationFormat::Csv
+        } else if self.tsv || self.output_format.tsv_output {
+            SerializationFormat::Tsv
         } else {
             // If no options were specified, attempt to parse from the input file extension
             if let Some(path) = &self.file {
@@ -184,6 +307,12 @@ impl Cli {
                         "json" => return SerializationFormat::Json,
                         "yaml" => return SerializationFormat::Yaml,
                         "toml" => return SerializationFormat::Toml,
+                        "cbor" => return SerializationFormat::Cbor,
+                        "msgpack" => return SerializationFormat::Msgpack,
+                        "ron" => return SerializationFormat::Ron,
+                        "json5" => return SerializationFormat::Json5,
+                        "csv" => return SerializationFormat::Csv,
+                        "tsv" => return SerializationFormat::Tsv,
                         _ => {}
                     };
                 }
@@ -209,21 +338,43 @@ fn init_log(verbosity: &Verbosity) -> Result<()> {
     .with_context(|| "Unable to initialize logger")
 }
 
-fn print(should_color: bool, lang: &'static str, value: impl AsRef<[u8]>) -> Result<()> {
+/// Write `value` to stdout, syntax-highlighted via `bat` when `should_color` is set and
+/// `lang` names a syntax `bat` actually ships (e.g. `"json"`, `"yaml"`, `"toml"`). Pass
+/// `None` for formats `bat` doesn't know how to highlight (e.g. RON) — asking `bat` to
+/// highlight an unregistered syntax doesn't error, it just writes nothing, so this falls
+/// back to a plain write instead of silently dropping the output.
+fn print(should_color: bool, lang: Option<&'static str>, value: impl AsRef<[u8]>) -> Result<()> {
     let buf = value.as_ref();
 
-    if should_color {
-        bat::PrettyPrinter::new()
-            .language(lang)
-            .input_from_bytes(buf)
-            .print()?;
-    } else {
-        stdout().write_all(buf)?;
+    match lang {
+        Some(lang) if should_color => {
+            bat::PrettyPrinter::new()
+                .language(lang)
+                .input_from_bytes(buf)
+                .print()?;
+        }
+        _ => {
+            stdout().write_all(buf)?;
+        }
     }
 
     Ok(())
 }
 
+/// Write a binary serialization format (e.g. CBOR, MessagePack) to stdout. Unlike `print`,
+/// this never runs the bytes through a syntax highlighter, and refuses to dump them to a
+/// terminal since they aren't meant to be read by a human.
+fn print_binary(format_name: &'static str, value: impl AsRef<[u8]>) -> Result<()> {
+    if stdout().is_terminal() {
+        return Err(anyhow!(
+            "refusing to print binary {format_name} output to a terminal; redirect it to a file or pipe instead"
+        ));
+    }
+
+    stdout().write_all(value.as_ref())?;
+    Ok(())
+}
+
 fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
     let output_format = cli.get_output_format();
 
@@ -250,6 +401,9 @@ fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
         SerializationFormat::Json => {
             for value in result_iterator {
                 match value {
+                    Ok(Value::String(s)) if cli.output_format.raw_output0 => {
+                        print!("{s}\0");
+                    }
                     Ok(Value::String(s)) if cli.output_format.raw_output => {
                         println!("{s}\n");
                     }
@@ -260,7 +414,7 @@ fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
                             serde_json::to_string_pretty::<Value>(&value)?
                         };
                         value.push('\n');
-                        print(should_color, "json", value)?;
+                        print(should_color, Some("json"), value)?;
                     }
                     Err(e) => eprintln!("Error: {e:?}"),
                 }
@@ -273,7 +427,7 @@ fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
                         let mut buf = b"---\n".to_vec();
                         serde_yaml::to_writer(&mut buf, &value).context("Write to output")?;
                         buf.push(b'\n');
-                        print(should_color, "yaml", buf)?;
+                        print(should_color, Some("yaml"), buf)?;
                     }
                     Err(e) => eprintln!("Error: {e:?}"),
                 }
@@ -284,7 +438,7 @@ fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
                 match value {
                     Ok(value) => {
                         if value.is_null() {
-                            print(should_color, "toml", "\"null\"\n")?;
+                            print(should_color, Some("toml"), "\"null\"\n")?;
                             return Ok(());
                         }
 
@@ -306,14 +460,156 @@ fn run_with_input(cli: Cli, input: impl Input) -> Result<()> {
                             )
                             .context("Serialize value with toml")?;
                             buf.push('\n');
-                            print(should_color, "toml", buf)?;
+                            print(should_color, Some("toml"), buf)?;
                         }
                     }
                     Err(e) => eprintln!("Error: {e:?}"),
                 }
             }
         }
+        SerializationFormat::Cbor => {
+            for value in result_iterator {
+                match value {
+                    Ok(value) => {
+                        let mut buf = Vec::new();
+                        ciborium::into_writer(&value, &mut buf)
+                            .context("Serialize value with cbor")?;
+                        print_binary("cbor", buf)?;
+                    }
+                    Err(e) => eprintln!("Error: {e:?}"),
+                }
+            }
+        }
+        SerializationFormat::Msgpack => {
+            for value in result_iterator {
+                match value {
+                    Ok(value) => {
+                        let buf =
+                            rmp_serde::to_vec(&value).context("Serialize value with msgpack")?;
+                        print_binary("msgpack", buf)?;
+                    }
+                    Err(e) => eprintln!("Error: {e:?}"),
+                }
+            }
+        }
+        SerializationFormat::Ron => {
+            for value in result_iterator {
+                match value {
+                    Ok(value) => {
+                        let mut buf = if cli.output_format.compact_output {
+                            ron::to_string(&value).context("Serialize value with ron")?
+                        } else {
+                            ron::ser::to_string_pretty(&value, ron::ser::PrettyConfig::default())
+                                .context("Serialize value with ron")?
+                        };
+                        buf.push('\n');
+                        print(should_color, None, buf)?;
+                    }
+                    Err(e) => eprintln!("Error: {e:?}"),
+                }
+            }
+        }
+        SerializationFormat::Json5 => {
+            // The `json5` crate has no pretty-printer, but plain JSON is valid JSON5, so
+            // honor `compact_output` by falling back to `serde_json`'s pretty printer.
+            for value in result_iterator {
+                match value {
+                    Ok(value) => {
+                        let mut buf = if cli.output_format.compact_output {
+                            json5::to_string(&value).context("Serialize value with json5")?
+                        } else {
+                            serde_json::to_string_pretty::<Value>(&value)
+                                .context("Serialize value with json5")?
+                        };
+                        buf.push('\n');
+                        print(should_color, Some("json"), buf)?;
+                    }
+                    Err(e) => eprintln!("Error: {e:?}"),
+                }
+            }
+        }
+        SerializationFormat::Csv | SerializationFormat::Tsv => {
+            let delimiter = if output_format == SerializationFormat::Tsv {
+                b'\t'
+            } else {
+                b','
+            };
+            for value in result_iterator {
+                match value {
+                    Ok(value) => write_csv(delimiter, &value)?,
+                    Err(e) => eprintln!("Error: {e:?}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes one CSV/TSV document to stdout: `value` must be an array of objects, which is
+/// written as a header row (the union of all keys, in first-seen order) followed by one
+/// delimited row per object, quoted/escaped per RFC 4180 by the `csv` crate.
+fn write_csv(delimiter: u8, value: &Value) -> Result<()> {
+    let rows = value
+        .as_array()
+        .ok_or_else(|| anyhow!("csv/tsv output requires the result to be an array of objects"))?;
+
+    let mut header: Vec<String> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for row in rows {
+        let obj = row
+            .as_object()
+            .ok_or_else(|| anyhow!("csv/tsv output requires every element to be an object"))?;
+        for key in obj.keys() {
+            let key = key.to_string();
+            if seen.insert(key.clone()) {
+                header.push(key);
+            }
+        }
+    }
+
+    // Validate every row against `header` up front, before opening the writer. `rows` is
+    // already fully materialized in memory, so there's no streaming benefit to interleaving
+    // this check with writing — and doing it afterward would leave a truncated CSV document
+    // on stdout if a later row turned out to be ragged.
+    for row in rows {
+        let obj = row.as_object().expect("checked above");
+        if obj.len() != header.len() {
+            return Err(anyhow!(
+                "csv/tsv output requires every object to have the same keys; expected {} keys, got {}",
+                header.len(),
+                obj.len()
+            ));
+        }
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(stdout());
+    writer.write_record(&header)?;
+    for row in rows {
+        let obj = row.as_object().expect("checked above");
+        let fields: std::collections::HashMap<String, String> = obj
+            .iter()
+            .map(|(k, v)| {
+                let field = match v {
+                    Value::Null => Ok(String::new()),
+                    Value::String(s) => Ok(s.to_string()),
+                    other => serde_json::to_string(other),
+                }?;
+                Ok((k.to_string(), field))
+            })
+            .collect::<std::result::Result<_, serde_json::Error>>()
+            .context("Serialize csv/tsv field")?;
+        let record = header.iter().map(|key| {
+            fields
+                .get(key)
+                .cloned()
+                .ok_or_else(|| anyhow!("csv/tsv output: row is missing key {key:?}"))
+        });
+        let record = record.collect::<Result<Vec<_>>>()?;
+        writer.write_record(record)?;
     }
+    writer.flush()?;
     Ok(())
 }
 
@@ -342,6 +638,31 @@ fn read_and_run(cli: Cli, mut reader: impl Read + BufRead) -> Result<()> {
             let mut input = String::new();
             reader.read_to_string(&mut input)?;
             run_with_maybe_null_input(cli, Tied::new(std::iter::once(Ok(Value::from(input)))))
+        } else if cli.input_format.raw_input0 {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            // Only drop the final chunk `split` always appends after a trailing `\0`
+            // (mirroring how `.lines()` doesn't yield an empty entry for a trailing `\n`) —
+            // a real `\0\0` elsewhere in the input is a legitimate empty-string record and
+            // must be kept.
+            let chunks: Vec<&[u8]> = if buf.is_empty() {
+                Vec::new()
+            } else {
+                let mut chunks: Vec<&[u8]> = buf.split(|&b| b == 0).collect();
+                if buf.last() == Some(&0) {
+                    chunks.pop();
+                }
+                chunks
+            };
+            let input: Vec<Result<Value, InputError>> = chunks
+                .into_iter()
+                .map(|chunk| {
+                    String::from_utf8(chunk.to_vec())
+                        .map(Value::from)
+                        .map_err(InputError::new)
+                })
+                .collect();
+            run_with_maybe_null_input(cli, Tied::new(input.into_iter()))
         } else {
             let input = reader
                 .lines()
@@ -351,15 +672,35 @@ fn read_and_run(cli: Cli, mut reader: impl Read + BufRead) -> Result<()> {
     } else {
         match cli.get_input_format() {
             SerializationFormat::Json => {
-                let input = serde_json::de::Deserializer::from_reader(reader)
-                    .into_iter::<Value>()
-                    .map(|r| r.map_err(InputError::new));
+                // Deserialize through `serde_path_to_error` so a failure on a deeply nested
+                // document reports the offending field (e.g. `.users[3].email`) rather than
+                // just a line number. One `Deserializer` is built up front and reused across
+                // pulls (rather than rebuilt per value) so its internal lookahead byte isn't
+                // discarded between values — dropping it would break concatenated JSON that
+                // isn't separated by whitespace (e.g. `12"foo"`).
+                let mut de = serde_json::de::Deserializer::from_reader(reader);
+                let mut done = false;
+                let input = std::iter::from_fn(move || {
+                    if done {
+                        return None;
+                    }
+                    match serde_path_to_error::deserialize::<_, Value>(&mut de) {
+                        Ok(value) => Some(Ok(value)),
+                        Err(e) if e.inner().is_eof() => {
+                            done = true;
+                            None
+                        }
+                        Err(e) => {
+                            done = true;
+                            Some(Err(InputError::new(e)))
+                        }
+                    }
+                });
                 run_with_maybe_slurp_null_input(cli, Tied::new(input))
             }
             SerializationFormat::Yaml => {
-                use serde::Deserialize;
                 let input = serde_yaml::Deserializer::from_reader(reader)
-                    .map(Value::deserialize)
+                    .map(serde_path_to_error::deserialize::<_, Value>)
                     .map(|r| r.map_err(InputError::new));
                 run_with_maybe_slurp_null_input(cli, Tied::new(input))
             }
@@ -374,8 +715,10 @@ fn read_and_run(cli: Cli, mut reader: impl Read + BufRead) -> Result<()> {
                             Ok(line) => {
                                 if line.trim() == "+++" {
                                     // Split on section dividers
-                                    let value: Result<Value, _> =
-                                        toml::from_str(&buf).map_err(InputError::new);
+                                    let value: Result<Value, _> = serde_path_to_error::deserialize(
+                                        toml::de::Deserializer::new(&buf),
+                                    )
+                                    .map_err(InputError::new);
                                     buf.clear();
                                     Some(value)
                                 } else {
@@ -389,6 +732,111 @@ fn read_and_run(cli: Cli, mut reader: impl Read + BufRead) -> Result<()> {
                     });
                 run_with_maybe_slurp_null_input(cli, Tied::new(input))
             }
+            SerializationFormat::Cbor => {
+                // Lazily decode one CBOR document per call so a long/slow-arriving stream
+                // doesn't have to be buffered in full before the query can start.
+                let mut reader = reader;
+                let mut done = false;
+                let input = std::iter::from_fn(move || {
+                    if done {
+                        return None;
+                    }
+                    match ciborium::from_reader::<Value, _>(&mut reader) {
+                        Ok(value) => Some(Ok(value)),
+                        Err(ciborium::de::Error::Io(e))
+                            if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                        {
+                            done = true;
+                            None
+                        }
+                        Err(e) => {
+                            done = true;
+                            Some(Err(InputError::new(e)))
+                        }
+                    }
+                });
+                run_with_maybe_slurp_null_input(cli, Tied::new(input))
+            }
+            SerializationFormat::Msgpack => {
+                // Lazily decode one MessagePack document per call; see the Cbor arm above.
+                let mut reader = reader;
+                let mut done = false;
+                let input = std::iter::from_fn(move || {
+                    if done {
+                        return None;
+                    }
+                    match rmp_serde::from_read::<_, Value>(&mut reader) {
+                        Ok(value) => Some(Ok(value)),
+                        Err(rmp_serde::decode::Error::InvalidMarkerRead(e))
+                            if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                        {
+                            done = true;
+                            None
+                        }
+                        Err(e) => {
+                            done = true;
+                            Some(Err(InputError::new(e)))
+                        }
+                    }
+                });
+                run_with_maybe_slurp_null_input(cli, Tied::new(input))
+            }
+            SerializationFormat::Ron => {
+                let mut buf = String::new();
+                reader.read_to_string(&mut buf)?;
+                let value: Result<Value, _> = ron::from_str(&buf).map_err(InputError::new);
+                run_with_maybe_slurp_null_input(cli, Tied::new(std::iter::once(value)))
+            }
+            SerializationFormat::Json5 => {
+                let mut buf = String::new();
+                reader.read_to_string(&mut buf)?;
+                let value: Result<Value, _> = json5::from_str(&buf).map_err(InputError::new);
+                run_with_maybe_slurp_null_input(cli, Tied::new(std::iter::once(value)))
+            }
+            format @ (SerializationFormat::Csv | SerializationFormat::Tsv) => {
+                let delimiter = if format == SerializationFormat::Tsv {
+                    b'\t'
+                } else {
+                    b','
+                };
+                let has_headers = !cli.input_format.no_header;
+                let mut csv_reader = csv::ReaderBuilder::new()
+                    .delimiter(delimiter)
+                    .has_headers(has_headers)
+                    .from_reader(reader);
+
+                // Share one `Rc<String>` per column across all rows instead of re-allocating
+                // the header text for every record.
+                let header_keys: Option<Vec<Rc<String>>> = if has_headers {
+                    match csv_reader.headers() {
+                        Ok(h) => Some(h.iter().map(|k| Rc::new(k.to_string())).collect()),
+                        Err(e) => {
+                            let err = Err(InputError::new(e));
+                            return run_with_maybe_slurp_null_input(
+                                cli,
+                                Tied::new(std::iter::once(err)),
+                            );
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                // `into_records()` reads and yields one record at a time from the
+                // underlying reader, so this stays lazy rather than buffering the file.
+                let input = csv_reader.into_records().map(move |record| {
+                    record.map_err(InputError::new).map(|record| match &header_keys {
+                        Some(keys) => keys
+                            .iter()
+                            .cloned()
+                            .zip(record.iter())
+                            .map(|(k, v)| (k, Value::from(v.to_string())))
+                            .collect(),
+                        None => record.iter().map(|v| Value::from(v.to_string())).collect(),
+                    })
+                });
+                run_with_maybe_slurp_null_input(cli, Tied::new(input))
+            }
         }
     }
 }
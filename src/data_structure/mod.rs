@@ -1,10 +1,190 @@
 pub mod undo;
 
-use std::{borrow::Borrow, rc::Rc};
+use std::{borrow::Borrow, hash::Hash, rc::Rc};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 pub type PVector<T> = imbl::Vector<T>;
 pub type PHashMap<K, V> = imbl::HashMap<K, V>;
 
+/// A persistent, order-preserving map. Keeps keys in insertion order (like a JSON/YAML/TOML
+/// object written by a human) while still offering O(1) lookup, by pairing a [`PVector`] of
+/// keys with a [`PHashMap`] for the actual values. Used as the backing store for `Value`'s
+/// object variant so round-tripping a document doesn't scramble the author's key order.
+#[derive(Clone, Debug)]
+pub struct POrderedMap<K, V> {
+    keys: PVector<K>,
+    values: PHashMap<K, V>,
+}
+
+impl<K, V> Default for POrderedMap<K, V> {
+    fn default() -> Self {
+        Self {
+            keys: PVector::new(),
+            values: PHashMap::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> POrderedMap<K, V> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.values.get(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.values.contains_key(key)
+    }
+
+    /// Inserts a value, appending the key to the end if it wasn't already present.
+    /// Updating an existing key keeps its original position.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.values.insert(key.clone(), value);
+        if old.is_none() {
+            self.keys.push_back(key);
+        }
+        old
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let old = self.values.remove(key);
+        if old.is_some() {
+            self.keys.retain(|k| k.borrow() != key);
+        }
+        old
+    }
+
+    /// Iterates entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.keys.iter().map(move |k| {
+            (
+                k,
+                self.values
+                    .get(k)
+                    .expect("POrderedMap keys and values got out of sync"),
+            )
+        })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> FromIterator<(K, V)> for POrderedMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> IntoIterator for POrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let items: Vec<_> = self
+            .keys
+            .iter()
+            .map(|k| {
+                let v = self
+                    .values
+                    .get(k)
+                    .expect("POrderedMap keys and values got out of sync")
+                    .clone();
+                (k.clone(), v)
+            })
+            .collect();
+        items.into_iter()
+    }
+}
+
+// Equality ignores key order, matching the semantics of the formats we round-trip
+// (two JSON objects with the same entries in a different order are equal).
+impl<K: Clone + Eq + Hash, V: Clone + PartialEq> PartialEq for POrderedMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone + Eq> Eq for POrderedMap<K, V> {}
+
+impl<K: Serialize + Clone + Eq + Hash, V: Serialize + Clone> Serialize for POrderedMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.iter() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for POrderedMap<K, V>
+where
+    K: Deserialize<'de> + Clone + Eq + Hash,
+    V: Deserialize<'de> + Clone,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct POrderedMapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+        impl<'de, K, V> Visitor<'de> for POrderedMapVisitor<K, V>
+        where
+            K: Deserialize<'de> + Clone + Eq + Hash,
+            V: Deserialize<'de> + Clone,
+        {
+            type Value = POrderedMap<K, V>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = POrderedMap::new();
+                while let Some((key, value)) = access.next_entry()? {
+                    map.insert(key, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(POrderedMapVisitor(std::marker::PhantomData))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct PStack<T, B = [T; 32]> {
     prev: Option<Rc<PStack<T, B>>>,
@@ -75,7 +255,32 @@ impl<T: Clone, B> PStack<T, B> {
 
 #[cfg(test)]
 mod test {
-    use super::PStack;
+    use super::{POrderedMap, PStack};
+
+    #[test]
+    fn test_ordered_map_preserves_insertion_order() {
+        let mut m = POrderedMap::new();
+        m.insert("z", 1);
+        m.insert("a", 2);
+        m.insert("m", 3);
+        assert_eq!(
+            vec![("z", 1), ("a", 2), ("m", 3)],
+            m.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+        );
+
+        // Updating an existing key keeps its original position.
+        m.insert("a", 20);
+        assert_eq!(
+            vec![("z", 1), ("a", 20), ("m", 3)],
+            m.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+        );
+
+        m.remove("z");
+        assert_eq!(
+            vec![("a", 20), ("m", 3)],
+            m.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+        );
+    }
 
     #[test]
     fn test_stack() {
@@ -0,0 +1,4 @@
+pub mod data_structure;
+pub mod value;
+
+pub use value::Value;
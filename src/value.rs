@@ -0,0 +1,198 @@
+use std::{fmt, rc::Rc};
+
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::data_structure::{POrderedMap, PVector};
+
+/// The backing store for [`Value::Object`]. Keyed by `Rc<String>` (consistent with
+/// `Value::String`) and backed by [`POrderedMap`] so objects keep their author's key order
+/// through a read-transform-write round trip instead of scrambling it.
+pub type Object = POrderedMap<Rc<String>, Value>;
+pub type Array = PVector<Value>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(Rc<String>),
+    Array(Array),
+    Object(Object),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Value::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(Rc::new(s))
+    }
+}
+
+impl FromIterator<Value> for Value {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        Value::Array(iter.into_iter().collect())
+    }
+}
+
+impl FromIterator<(Rc<String>, Value)> for Value {
+    fn from_iter<T: IntoIterator<Item = (Rc<String>, Value)>>(iter: T) -> Self {
+        Value::Object(iter.into_iter().collect())
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Number(n) => n.serialize(serializer),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Array(a) => {
+                let mut seq = serializer.serialize_seq(Some(a.len()))?;
+                for v in a.iter() {
+                    seq.serialize_element(v)?;
+                }
+                seq.end()
+            }
+            // Objects serialize their entries in insertion order, not hash order, since
+            // `Object` is a `POrderedMap`.
+            Value::Object(o) => o.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a valid value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+                Ok(Value::Bool(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+                Ok(Value::Number(v.into()))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+                Ok(Value::Number(v.into()))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+                Ok(serde_json::Number::from_f64(v)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+                Ok(Value::from(v.to_string()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Value, E> {
+                Ok(Value::from(v))
+            }
+
+            fn visit_unit<E>(self) -> Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_none<E>(self) -> Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+                Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+                let mut vec = PVector::new();
+                while let Some(v) = seq.next_element()? {
+                    vec.push_back(v);
+                }
+                Ok(Value::Array(vec))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+                let mut obj = Object::new();
+                while let Some((k, v)) = map.next_entry::<String, Value>()? {
+                    obj.insert(Rc::new(k), v);
+                }
+                Ok(Value::Object(obj))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::{Object, Value};
+
+    #[test]
+    fn object_round_trip_preserves_key_order() {
+        let json = r#"{"z":1,"a":2,"m":3}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+
+        let keys: Vec<_> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.to_string())
+            .collect();
+        assert_eq!(vec!["z", "a", "m"], keys);
+
+        assert_eq!(json, serde_json::to_string(&value).unwrap());
+    }
+
+    #[test]
+    fn object_update_keeps_position() {
+        let mut obj = Object::new();
+        obj.insert(Rc::new("z".to_string()), Value::from("1".to_string()));
+        obj.insert(Rc::new("a".to_string()), Value::from("2".to_string()));
+
+        // `|= ` updates an existing key's value without moving it.
+        obj.insert(Rc::new("z".to_string()), Value::from("99".to_string()));
+
+        let keys: Vec<_> = obj.keys().map(|k| k.to_string()).collect();
+        assert_eq!(vec!["z", "a"], keys);
+        assert_eq!(
+            Some(&Value::from("99".to_string())),
+            obj.get(&Rc::new("z".to_string()))
+        );
+    }
+}